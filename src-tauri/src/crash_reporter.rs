@@ -0,0 +1,200 @@
+use once_cell::sync::Lazy;
+use sentry::protocol::Event;
+use sqlx::sqlite::SqlitePoolOptions;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use tauri::{AppHandle, Manager};
+
+/// 建置時若未以 `SCRIPTHUB_SENTRY_DSN` 環境變數指定 DSN，則退回此開發用預設值
+const DEFAULT_CRASH_REPORT_DSN: &str = "https://examplePublicKey@o0.ingest.sentry.io/0";
+
+/// 讀取可設定的崩潰回報 DSN：執行期環境變數優先，其次是建置期寫入的值，
+/// 最後才退回開發用的預設 DSN
+fn crash_report_dsn() -> String {
+    std::env::var("SCRIPTHUB_SENTRY_DSN")
+        .ok()
+        .filter(|dsn| !dsn.is_empty())
+        .or_else(|| option_env!("SCRIPTHUB_SENTRY_DSN").map(str::to_string))
+        .unwrap_or_else(|| DEFAULT_CRASH_REPORT_DSN.to_string())
+}
+
+/// 使用者是否同意回報，預設關閉；啟動時先同步讀取一次磁碟上的設定。
+/// `before_send` 在每個事件送出前都會檢查這個旗標，因此 `set_crash_reporting_enabled`
+/// 可以在不重啟 client 的情況下即時生效（開啟後立刻開始回報、關閉後立刻停止回報）。
+static CRASH_REPORTING_ENABLED: Lazy<AtomicBool> =
+    Lazy::new(|| AtomicBool::new(read_consent_from_disk()));
+
+/// 在 Tauri runtime 建立前，直接從腳本資料庫同步讀取使用者同意狀態
+fn read_consent_from_disk() -> bool {
+    let Some(data_dir) = dirs::data_dir() else {
+        return false;
+    };
+    let db_path = data_dir.join("com.scripthub.app").join("scripthub.db");
+    let Ok(conn) = rusqlite::Connection::open(db_path) else {
+        return false;
+    };
+    conn.query_row(
+        "SELECT value FROM settings WHERE key = 'crash_reporting_enabled'",
+        [],
+        |row| row.get::<_, String>(0),
+    )
+    .map(|value| value == "1")
+    .unwrap_or(false)
+}
+
+/// 目前金鑰庫中 GitHub Token 的快取。`before_send` 在 Sentry 自動的 panic 處理路徑上
+/// 也會被呼叫，這時行程可能已處於不穩定狀態，不適合再去敲系統金鑰庫（可能跳出互動式
+/// 權限視窗而卡住）；因此只在 token 被寫入/刪除時（[`save_github_token`] /
+/// [`delete_github_token`]）才更新這份快取，`before_send` 只讀取它。
+static CACHED_GITHUB_TOKEN: Lazy<Mutex<Option<String>>> =
+    Lazy::new(|| Mutex::new(crate::github_auth::load_github_token()));
+
+/// 供 `github_auth` 在寫入或刪除金鑰庫後同步更新快取
+pub fn update_cached_github_token(token: Option<String>) {
+    if let Ok(mut guard) = CACHED_GITHUB_TOKEN.lock() {
+        *guard = token;
+    }
+}
+
+/// 收集所有需要在回報中遮蔽的 GitHub Token：環境變數，以及快取中的金鑰庫 token
+fn collect_github_secrets() -> Vec<String> {
+    let mut secrets: Vec<String> = ["GITHUB_TOKEN", "GH_TOKEN"]
+        .iter()
+        .filter_map(|key| std::env::var(key).ok())
+        .filter(|value| !value.is_empty())
+        .collect();
+
+    if let Ok(guard) = CACHED_GITHUB_TOKEN.lock() {
+        if let Some(token) = guard.as_ref() {
+            if !token.is_empty() {
+                secrets.push(token.clone());
+            }
+        }
+    }
+
+    secrets
+}
+
+fn redact(text: &mut String, secrets: &[String]) {
+    for secret in secrets {
+        if text.contains(secret.as_str()) {
+            *text = text.replace(secret.as_str(), "[redacted]");
+        }
+    }
+}
+
+/// 清除事件中任何可能夾帶 GitHub Token 的欄位：訊息、例外、breadcrumb 與額外資料
+fn scrub_github_token(event: &mut Event<'static>) {
+    let secrets = collect_github_secrets();
+    if secrets.is_empty() {
+        return;
+    }
+
+    if let Some(message) = event.message.as_mut() {
+        redact(message, &secrets);
+    }
+
+    for exception in event.exception.values.iter_mut() {
+        if let Some(value) = exception.value.as_mut() {
+            redact(value, &secrets);
+        }
+    }
+
+    for breadcrumb in event.breadcrumbs.values.iter_mut() {
+        if let Some(message) = breadcrumb.message.as_mut() {
+            redact(message, &secrets);
+        }
+        for value in breadcrumb.data.values_mut() {
+            if let serde_json::Value::String(text) = value {
+                redact(text, &secrets);
+            }
+        }
+    }
+
+    for value in event.extra.values_mut() {
+        if let serde_json::Value::String(text) = value {
+            redact(text, &secrets);
+        }
+    }
+
+    for value in event.tags.values_mut() {
+        redact(value, &secrets);
+    }
+}
+
+/// `before_send` 鉤子：未同意回報時整筆事件丟棄，否則先遮蔽 token 再送出
+fn before_send(mut event: Event<'static>) -> Option<Event<'static>> {
+    if !CRASH_REPORTING_ENABLED.load(Ordering::Relaxed) {
+        return None;
+    }
+
+    scrub_github_token(&mut event);
+    Some(event)
+}
+
+/// 持有崩潰回報所需的兩個 guard。兩者都必須活到程式結束才能各自發揮作用：
+/// `sentry` guard 負責在結束前把緩衝的事件送出，`minidump` guard 則是 minidump
+/// watcher 本身（`sentry-rust-minidump` 文件要求呼叫端保留它，一旦被 drop
+/// 就會立刻停止攔截原生崩潰）。
+pub struct CrashReportingGuard {
+    _sentry: sentry::ClientInitGuard,
+    _minidump: Box<dyn std::any::Any>,
+}
+
+/// 初始化崩潰回報（Rust panic 與 webview 端的原生 minidump）。
+/// client 永遠建立（否則之後開啟同意時就沒有 panic hook 可用），實際是否回報交由
+/// `before_send` 依據 [`CRASH_REPORTING_ENABLED`] 即時判斷。
+pub fn init() -> CrashReportingGuard {
+    // 提前觸發一次金鑰庫讀取並填入快取，避免日後第一次 panic 時才去敲金鑰庫
+    Lazy::force(&CACHED_GITHUB_TOKEN);
+    // 同理，啟動時先同步讀取一次使用者同意狀態
+    Lazy::force(&CRASH_REPORTING_ENABLED);
+
+    let sentry_guard = sentry::init((
+        crash_report_dsn(),
+        sentry::ClientOptions {
+            release: sentry::release_name!(),
+            before_send: Some(Arc::new(before_send)),
+            ..Default::default()
+        },
+    ));
+
+    // 捕捉 webview / 原生行程崩潰產生的 minidump 並一併上傳；guard 必須保留，
+    // 否則 watcher 在這個語句結束時就會被立刻拆除
+    let minidump_guard = sentry_rust_minidump::init(&sentry_guard);
+
+    CrashReportingGuard {
+        _sentry: sentry_guard,
+        _minidump: Box::new(minidump_guard),
+    }
+}
+
+/// 供前端設定頁即時開關崩潰回報，同時寫入腳本資料庫的 settings 表
+#[tauri::command]
+pub async fn set_crash_reporting_enabled(app_handle: AppHandle, enabled: bool) -> Result<(), String> {
+    let db_path = app_handle
+        .path()
+        .app_data_dir()
+        .map_err(|e| e.to_string())?
+        .join("scripthub.db");
+    let url = format!("sqlite:{}", db_path.display());
+
+    let pool = SqlitePoolOptions::new()
+        .max_connections(1)
+        .connect(&url)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    sqlx::query(
+        "INSERT INTO settings (key, value) VALUES ('crash_reporting_enabled', ?) \
+         ON CONFLICT(key) DO UPDATE SET value = excluded.value",
+    )
+    .bind(if enabled { "1" } else { "0" })
+    .execute(&pool)
+    .await
+    .map_err(|e| e.to_string())?;
+
+    // 立即生效，不需等待下次啟動
+    CRASH_REPORTING_ENABLED.store(enabled, Ordering::Relaxed);
+    Ok(())
+}
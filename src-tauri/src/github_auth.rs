@@ -0,0 +1,34 @@
+use keyring::Entry;
+
+const KEYRING_SERVICE: &str = "ScriptHub/github.com";
+const KEYRING_USER: &str = "github-token";
+
+fn token_entry() -> Result<Entry, String> {
+    Entry::new(KEYRING_SERVICE, KEYRING_USER).map_err(|e| e.to_string())
+}
+
+/// 將 GitHub Token 寫入系統金鑰庫（Windows Credential Manager / macOS Keychain / Secret Service）
+#[tauri::command]
+pub fn save_github_token(token: String) -> Result<(), String> {
+    token_entry()?.set_password(&token).map_err(|e| e.to_string())?;
+    crate::crash_reporter::update_cached_github_token(Some(token));
+    Ok(())
+}
+
+/// 從系統金鑰庫讀取先前儲存的 GitHub Token
+#[tauri::command]
+pub fn load_github_token() -> Option<String> {
+    token_entry().ok()?.get_password().ok()
+}
+
+/// 從系統金鑰庫刪除 GitHub Token
+#[tauri::command]
+pub fn delete_github_token() -> Result<(), String> {
+    match token_entry()?.delete_credential() {
+        Ok(()) => {}
+        Err(keyring::Error::NoEntry) => {}
+        Err(e) => return Err(e.to_string()),
+    }
+    crate::crash_reporter::update_cached_github_token(None);
+    Ok(())
+}
@@ -0,0 +1,99 @@
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+/// ScriptHub 的 GitHub OAuth App client_id（device flow 不需要 client secret）
+const GITHUB_CLIENT_ID: &str = "Iv1.scripthub0000000";
+
+/// 啟動 device flow 回傳給前端的資訊，前端需顯示 `user_code` 並開啟 `verification_uri`
+#[derive(Clone, Serialize)]
+pub struct DeviceAuthorization {
+    pub device_code: String,
+    pub user_code: String,
+    pub verification_uri: String,
+    pub expires_in: u64,
+    pub interval: u64,
+}
+
+#[derive(Deserialize)]
+struct DeviceCodeResponse {
+    device_code: String,
+    user_code: String,
+    verification_uri: String,
+    expires_in: u64,
+    interval: u64,
+}
+
+#[derive(Deserialize)]
+struct PollResponse {
+    access_token: Option<String>,
+    error: Option<String>,
+    interval: Option<u64>,
+}
+
+/// 向 GitHub 發起 device flow，取得 device_code / user_code 供前端顯示與導向瀏覽器
+#[tauri::command]
+pub async fn start_github_oauth() -> Result<DeviceAuthorization, String> {
+    let client = reqwest::Client::new();
+    let response: DeviceCodeResponse = client
+        .post("https://github.com/login/device/code")
+        .header("Accept", "application/json")
+        .form(&[("client_id", GITHUB_CLIENT_ID), ("scope", "repo")])
+        .send()
+        .await
+        .map_err(|e| e.to_string())?
+        .json()
+        .await
+        .map_err(|e| e.to_string())?;
+
+    Ok(DeviceAuthorization {
+        device_code: response.device_code,
+        user_code: response.user_code,
+        verification_uri: response.verification_uri,
+        expires_in: response.expires_in,
+        interval: response.interval,
+    })
+}
+
+/// 輪詢 GitHub 直到使用者完成授權，依 `authorization_pending` / `slow_down` 調整輪詢間隔
+#[tauri::command]
+pub async fn poll_github_oauth(device_code: String, interval: u64) -> Result<String, String> {
+    let client = reqwest::Client::new();
+    let mut interval = interval.max(1);
+
+    loop {
+        tokio::time::sleep(Duration::from_secs(interval)).await;
+
+        let response: PollResponse = client
+            .post("https://github.com/login/oauth/access_token")
+            .header("Accept", "application/json")
+            .form(&[
+                ("client_id", GITHUB_CLIENT_ID),
+                ("device_code", device_code.as_str()),
+                (
+                    "grant_type",
+                    "urn:ietf:params:oauth:grant-type:device_code",
+                ),
+            ])
+            .send()
+            .await
+            .map_err(|e| e.to_string())?
+            .json()
+            .await
+            .map_err(|e| e.to_string())?;
+
+        if let Some(token) = response.access_token {
+            return Ok(token);
+        }
+
+        match response.error.as_deref() {
+            Some("authorization_pending") => continue,
+            Some("slow_down") => {
+                interval = response.interval.unwrap_or(interval + 5);
+            }
+            Some("expired_token") => return Err("裝置授權碼已過期，請重新開始".to_string()),
+            Some("access_denied") => return Err("使用者拒絕授權".to_string()),
+            Some(other) => return Err(format!("GitHub OAuth 錯誤: {}", other)),
+            None => return Err("GitHub OAuth 回應格式不正確".to_string()),
+        }
+    }
+}
@@ -1,12 +1,24 @@
-use tauri::{
-    menu::{Menu, MenuItem},
-    tray::TrayIconBuilder,
-    Manager,
-};
+use tauri::{menu::Menu, tray::TrayIconBuilder, Emitter, Manager};
 use tauri_plugin_autostart::MacosLauncher;
+use tauri_plugin_window_state::{AppHandleExt as _, StateFlags};
 use std::process::Command;
 use std::env;
 
+mod updater;
+use updater::{check_for_update, install_update};
+
+mod github_auth;
+use github_auth::{delete_github_token, load_github_token, save_github_token};
+
+mod github_oauth;
+use github_oauth::{poll_github_oauth, start_github_oauth};
+
+mod tray;
+use tray::rebuild_tray_menu;
+
+mod crash_reporter;
+use crash_reporter::set_crash_reporting_enabled;
+
 /// 從 Git Credential Manager 獲取 GitHub Token
 #[tauri::command]
 fn get_github_credential() -> Result<Option<String>, String> {
@@ -67,9 +79,9 @@ fn get_github_env_token() -> Option<String> {
         .or_else(|| env::var("GH_TOKEN").ok())
 }
 
-/// 驗證 GitHub Token 是否有效
+/// 驗證 GitHub Token 是否有效，`remember` 為 true 時會將通過驗證的 token 存入系統金鑰庫
 #[tauri::command]
-async fn verify_github_token(token: String) -> Result<bool, String> {
+async fn verify_github_token(token: String, remember: bool) -> Result<bool, String> {
     let client = reqwest::Client::new();
     let response = client
         .get("https://api.github.com/user")
@@ -79,7 +91,12 @@ async fn verify_github_token(token: String) -> Result<bool, String> {
         .await
         .map_err(|e| e.to_string())?;
 
-    Ok(response.status().is_success())
+    let valid = response.status().is_success();
+    if valid && remember {
+        github_auth::save_github_token(token)?;
+    }
+
+    Ok(valid)
 }
 
 
@@ -95,6 +112,10 @@ fn quit_app(app_handle: tauri::AppHandle) {
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
+    // 在建立 Tauri runtime 之前就初始化崩潰回報，才能捕捉最早期的 panic；
+    // guard 需保留至程式結束才 drop，以確保背景執行緒把最後的事件送出
+    let _crash_reporting_guard = crash_reporter::init();
+
     tauri::Builder::default()
         .plugin(tauri_plugin_clipboard_manager::init())
         .plugin(tauri_plugin_dialog::init())
@@ -105,23 +126,33 @@ pub fn run() {
             MacosLauncher::LaunchAgent,
             Some(vec!["--minimized"]),
         ))
+        .plugin(tauri_plugin_updater::Builder::new().build())
+        .plugin(tauri_plugin_window_state::Builder::default().build())
         .invoke_handler(tauri::generate_handler![
             get_github_credential,
             get_github_env_token,
             verify_github_token,
             minimize_window,
-            quit_app
+            quit_app,
+            check_for_update,
+            install_update,
+            save_github_token,
+            load_github_token,
+            delete_github_token,
+            start_github_oauth,
+            poll_github_oauth,
+            rebuild_tray_menu,
+            set_crash_reporting_enabled
         ])
+        .manage(tray::UpdateAvailable(std::sync::Mutex::new(false)))
         .setup(|app| {
-            // 創建托盤右鍵菜單
-            let show_item = MenuItem::with_id(app, "show", "顯示主窗口", true, None::<&str>)?;
-            let quit_item = MenuItem::with_id(app, "quit", "退出", true, None::<&str>)?;
-            let menu = Menu::with_items(app, &[&show_item, &quit_item])?;
+            // 托盤選單改由腳本資料庫動態組裝，啟動時先放一個空殼，setup 完成後立即刷新
+            let placeholder_menu = Menu::new(app)?;
 
             // 創建托盤圖標
-            let _tray = TrayIconBuilder::new()
+            let _tray = TrayIconBuilder::with_id("main-tray")
                 .icon(app.default_window_icon().unwrap().clone())
-                .menu(&menu)
+                .menu(&placeholder_menu)
                 .show_menu_on_left_click(false)
                 .tooltip("ScriptHub - 腳本管理器")
                 .on_tray_icon_event(|tray, event| {
@@ -135,7 +166,8 @@ pub fn run() {
                     }
                 })
                 .on_menu_event(|app, event| {
-                    match event.id.as_ref() {
+                    let id = event.id.as_ref();
+                    match id {
                         "show" => {
                             if let Some(window) = app.get_webview_window("main") {
                                 let _ = window.show();
@@ -145,11 +177,71 @@ pub fn run() {
                         "quit" => {
                             app.exit(0);
                         }
+                        "update_available" => {
+                            if let Some(window) = app.get_webview_window("main") {
+                                let _ = window.show();
+                                let _ = window.set_focus();
+                            }
+                            let _ = app.emit("update://requested", ());
+                        }
+                        "refresh_scripts" => {
+                            let app_handle = app.clone();
+                            tauri::async_runtime::spawn(async move {
+                                let _ = tray::rebuild(&app_handle).await;
+                            });
+                        }
+                        _ if id.starts_with("script:") => {
+                            tray::handle_script_menu_event(app, id);
+                        }
                         _ => {}
                     }
                 })
                 .build(app)?;
 
+            // 建立托盤專用的資料庫連線池（之後每次刷新托盤都重複使用，不再每次重連），
+            // 完成後立即依腳本資料庫內容組裝一次托盤選單。即使連線池建立失敗（例如
+            // 首次啟動時資料庫檔案還不存在），仍要呼叫 rebuild 讓托盤至少換掉啟動時
+            // 的空白 placeholder 選單，換成有「顯示主窗口」/「退出」的選單，
+            // 而不是永遠卡在一個右鍵什麼都點不到的托盤
+            let app_handle = app.handle().clone();
+            tauri::async_runtime::spawn(async move {
+                if let Err(e) = tray::init_pool(&app_handle).await.map(|pool| {
+                    app_handle.manage(pool);
+                }) {
+                    eprintln!("建立托盤資料庫連線池失敗，托盤將退回基本選單: {}", e);
+                }
+                let _ = tray::rebuild(&app_handle).await;
+            });
+
+            // 啟動時於背景檢查更新，發現新版本則通知托盤
+            updater::spawn_background_check(app.handle().clone());
+
+            // 攔截主窗口的關閉請求，改為隱藏至托盤而非結束程式。
+            // `on_window_event` 只能掛一個回呼，這裡的閉包會整個取代
+            // tauri-plugin-window-state 建立視窗時掛上的監聽器，所以同時手動呼叫
+            // 它的儲存邏輯，確保幾何狀態仍會被持久化而不是悄悄停止追蹤
+            if let Some(window) = app.get_webview_window("main") {
+                let event_window = window.clone();
+                window.on_window_event(move |event| {
+                    match event {
+                        tauri::WindowEvent::CloseRequested { api, .. } => {
+                            let _ = event_window.app_handle().save_window_state(StateFlags::all());
+                            api.prevent_close();
+                            let _ = event_window.hide();
+                        }
+                        tauri::WindowEvent::Resized(_) | tauri::WindowEvent::Moved(_) => {
+                            let _ = event_window.app_handle().save_window_state(StateFlags::all());
+                        }
+                        _ => {}
+                    }
+                });
+
+                // 延續 autostart 的 --minimized 參數：啟動時保持隱藏
+                if env::args().any(|arg| arg == "--minimized") {
+                    let _ = window.hide();
+                }
+            }
+
             Ok(())
         })
         .run(tauri::generate_context!())
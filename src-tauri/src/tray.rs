@@ -0,0 +1,144 @@
+use sqlx::sqlite::{SqliteConnectOptions, SqlitePool, SqlitePoolOptions};
+use std::str::FromStr;
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::menu::{Menu, MenuItem, PredefinedMenuItem};
+use tauri::{AppHandle, Emitter, Manager, Wry};
+
+/// 是否已偵測到更新，供托盤選單決定是否顯示「有更新可用」項目
+pub struct UpdateAvailable(pub Mutex<bool>);
+
+/// 托盤自己的腳本資料庫連線池，在 `setup()` 建立一次並透過 managed state 重複使用，
+/// 避免每次刷新托盤（啟動、「刷新列表」、背景更新檢查）都重新開一條連線去搶
+/// `tauri_plugin_sql` 本身持有的那個 pool
+pub struct ScriptDbPool(pub SqlitePool);
+
+const MAX_RECENT_SCRIPTS: i64 = 5;
+
+struct RecentScript {
+    id: i64,
+    name: String,
+}
+
+/// 建立托盤查詢專用的連線池；設定 busy_timeout 以便與 `tauri_plugin_sql` 的寫入並存
+pub async fn init_pool(app: &AppHandle) -> Result<ScriptDbPool, String> {
+    let db_path = app
+        .path()
+        .app_data_dir()
+        .map_err(|e| e.to_string())?
+        .join("scripthub.db");
+
+    let options = SqliteConnectOptions::from_str(&format!("sqlite:{}", db_path.display()))
+        .map_err(|e| e.to_string())?
+        .busy_timeout(Duration::from_secs(5));
+
+    let pool = SqlitePoolOptions::new()
+        .max_connections(1)
+        .connect_with(options)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    Ok(ScriptDbPool(pool))
+}
+
+/// 從腳本資料庫查詢最近使用的腳本，重用 managed 的連線池而非每次重新連線
+async fn fetch_recent_scripts(app: &AppHandle) -> Result<Vec<RecentScript>, String> {
+    let state = app
+        .try_state::<ScriptDbPool>()
+        .ok_or_else(|| "托盤資料庫連線池尚未就緒".to_string())?;
+    let pool = &state.0;
+
+    let rows: Vec<(i64, String)> = sqlx::query_as(
+        "SELECT id, name FROM scripts ORDER BY last_run_at DESC LIMIT ?",
+    )
+    .bind(MAX_RECENT_SCRIPTS)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| e.to_string())?;
+
+    Ok(rows
+        .into_iter()
+        .map(|(id, name)| RecentScript { id, name })
+        .collect())
+}
+
+/// 依目前資料庫內容與更新狀態重新組裝托盤選單
+async fn build_menu(app: &AppHandle) -> tauri::Result<Menu<Wry>> {
+    let scripts = fetch_recent_scripts(app).await.unwrap_or_else(|e| {
+        eprintln!("查詢最近使用的腳本失敗，托盤將顯示空列表: {}", e);
+        Vec::new()
+    });
+
+    let mut script_items = Vec::with_capacity(scripts.len());
+    for script in &scripts {
+        script_items.push(MenuItem::with_id(
+            app,
+            format!("script:{}", script.id),
+            &script.name,
+            true,
+            None::<&str>,
+        )?);
+    }
+
+    let refresh_item = MenuItem::with_id(app, "refresh_scripts", "刷新列表", true, None::<&str>)?;
+    let show_item = MenuItem::with_id(app, "show", "顯示主窗口", true, None::<&str>)?;
+    let quit_item = MenuItem::with_id(app, "quit", "退出", true, None::<&str>)?;
+    let separator = PredefinedMenuItem::separator(app)?;
+
+    let menu = Menu::new(app)?;
+    for item in &script_items {
+        menu.append(item)?;
+    }
+    if !script_items.is_empty() {
+        menu.append(&separator)?;
+    }
+    menu.append(&refresh_item)?;
+    menu.append(&separator)?;
+
+    let update_available = app
+        .state::<UpdateAvailable>()
+        .0
+        .lock()
+        .map(|guard| *guard)
+        .unwrap_or(false);
+    if update_available {
+        let update_item =
+            MenuItem::with_id(app, "update_available", "有更新可用", true, None::<&str>)?;
+        menu.append(&update_item)?;
+    }
+
+    menu.append(&show_item)?;
+    menu.append(&quit_item)?;
+
+    Ok(menu)
+}
+
+/// 重新查詢腳本資料庫並套用新的托盤選單
+pub async fn rebuild(app: &AppHandle) -> tauri::Result<()> {
+    let menu = build_menu(app).await?;
+    if let Some(tray) = app.tray_by_id("main-tray") {
+        tray.set_menu(Some(menu))?;
+    }
+    Ok(())
+}
+
+/// 標記目前是否有更新可用，並重新整理托盤選單以反映此狀態
+pub async fn set_update_available(app: &AppHandle, available: bool) {
+    if let Ok(mut guard) = app.state::<UpdateAvailable>().0.lock() {
+        *guard = available;
+    }
+    let _ = rebuild(app).await;
+}
+
+/// 提供給前端的指令：在腳本新增或重新命名後刷新托盤選單
+#[tauri::command]
+pub async fn rebuild_tray_menu(app_handle: AppHandle) -> Result<(), String> {
+    rebuild(&app_handle).await.map_err(|e| e.to_string())
+}
+
+/// 處理托盤選單中的腳本項目點擊：向前端送出執行事件
+pub fn handle_script_menu_event(app: &AppHandle, menu_id: &str) {
+    if let Some(id) = menu_id.strip_prefix("script:") {
+        let _ = app.emit("script://run", id.to_string());
+    }
+}
@@ -0,0 +1,92 @@
+use tauri::{AppHandle, Emitter, Manager};
+use tauri_plugin_updater::UpdaterExt;
+
+/// 可提供給前端的更新資訊
+#[derive(Clone, serde::Serialize)]
+pub struct UpdateInfo {
+    pub version: String,
+    pub notes: Option<String>,
+    pub pub_date: Option<String>,
+}
+
+/// 下載進度事件，透過 `update://progress` 發送給前端
+#[derive(Clone, serde::Serialize)]
+struct UpdateProgress {
+    downloaded: usize,
+    total: Option<u64>,
+}
+
+/// 檢查是否有新版本可用（不下載）
+#[tauri::command]
+pub async fn check_for_update(app_handle: AppHandle) -> Result<Option<UpdateInfo>, String> {
+    let update = app_handle
+        .updater()
+        .map_err(|e| e.to_string())?
+        .check()
+        .await
+        .map_err(|e| e.to_string())?;
+
+    Ok(update.map(|u| UpdateInfo {
+        version: u.version,
+        notes: u.body,
+        pub_date: u.date.map(|d| d.to_string()),
+    }))
+}
+
+/// 下載並安裝更新，完成後重啟應用程式
+#[tauri::command]
+pub async fn install_update(app_handle: AppHandle) -> Result<(), String> {
+    let update = app_handle
+        .updater()
+        .map_err(|e| e.to_string())?
+        .check()
+        .await
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| "目前已是最新版本".to_string())?;
+
+    let mut downloaded = 0usize;
+    let app_for_progress = app_handle.clone();
+
+    update
+        .download_and_install(
+            move |chunk_length, content_length| {
+                downloaded += chunk_length;
+                let _ = app_for_progress.emit(
+                    "update://progress",
+                    UpdateProgress {
+                        downloaded,
+                        total: content_length,
+                    },
+                );
+            },
+            || {},
+        )
+        .await
+        .map_err(|e| e.to_string())?;
+
+    app_handle.restart();
+}
+
+/// 在背景檢查更新，若發現新版本則在托盤選單加入「有更新可用」項目
+pub fn spawn_background_check(app_handle: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        let updater = match app_handle.updater() {
+            Ok(updater) => updater,
+            Err(e) => {
+                eprintln!("更新器初始化失敗: {}", e);
+                return;
+            }
+        };
+
+        match updater.check().await {
+            Ok(Some(update)) => {
+                let _ = app_handle.emit("update://available", update.version.clone());
+                crate::tray::set_update_available(&app_handle, true).await;
+            }
+            Ok(None) => {}
+            Err(e) => {
+                eprintln!("背景更新檢查失敗: {}", e);
+            }
+        }
+    });
+}